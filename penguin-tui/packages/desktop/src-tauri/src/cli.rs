@@ -2,6 +2,10 @@ use tauri::{AppHandle, Manager, path::BaseDirectory};
 use tauri_plugin_shell::{ShellExt, process::Command};
 
 const CLI_INSTALL_DIR: &str = ".opencode/bin";
+
+#[cfg(windows)]
+const CLI_BINARY_NAME: &str = "opencode.exe";
+#[cfg(not(windows))]
 const CLI_BINARY_NAME: &str = "opencode";
 
 #[derive(serde::Deserialize)]
@@ -25,12 +29,22 @@ pub async fn get_config(app: &AppHandle) -> Option<Config> {
         .and_then(|s| serde_json::from_str::<Config>(&s).ok())
 }
 
+/// Per-user directory the CLI is installed into: `%LOCALAPPDATA%\opencode\bin`
+/// on Windows, `~/.opencode/bin` elsewhere.
+fn get_cli_install_dir() -> Option<std::path::PathBuf> {
+    #[cfg(windows)]
+    return std::env::var("LOCALAPPDATA")
+        .ok()
+        .map(|dir| std::path::PathBuf::from(dir).join("opencode").join("bin"));
+
+    #[cfg(not(windows))]
+    return std::env::var("HOME")
+        .ok()
+        .map(|home| std::path::PathBuf::from(home).join(CLI_INSTALL_DIR));
+}
+
 fn get_cli_install_path() -> Option<std::path::PathBuf> {
-    std::env::var("HOME").ok().map(|home| {
-        std::path::PathBuf::from(home)
-            .join(CLI_INSTALL_DIR)
-            .join(CLI_BINARY_NAME)
-    })
+    get_cli_install_dir().map(|dir| dir.join(CLI_BINARY_NAME))
 }
 
 pub fn get_sidecar_path(app: &tauri::AppHandle) -> std::path::PathBuf {
@@ -52,15 +66,20 @@ const INSTALL_SCRIPT: &str = include_str!("../../../../install");
 
 #[tauri::command]
 pub fn install_cli(app: tauri::AppHandle) -> Result<String, String> {
-    if cfg!(not(unix)) {
-        return Err("CLI installation is only supported on macOS & Linux".to_string());
-    }
-
     let sidecar = get_sidecar_path(&app);
     if !sidecar.exists() {
         return Err("Sidecar binary not found".to_string());
     }
 
+    #[cfg(windows)]
+    return install_cli_windows(&sidecar);
+
+    #[cfg(not(windows))]
+    return install_cli_unix(&sidecar);
+}
+
+#[cfg(not(windows))]
+fn install_cli_unix(sidecar: &std::path::Path) -> Result<String, String> {
     let temp_script = std::env::temp_dir().join("opencode-install.sh");
     std::fs::write(&temp_script, INSTALL_SCRIPT)
         .map_err(|e| format!("Failed to write install script: {}", e))?;
@@ -74,7 +93,7 @@ pub fn install_cli(app: tauri::AppHandle) -> Result<String, String> {
 
     let output = std::process::Command::new(&temp_script)
         .arg("--binary")
-        .arg(&sidecar)
+        .arg(sidecar)
         .output()
         .map_err(|e| format!("Failed to run install script: {}", e))?;
 
@@ -91,6 +110,137 @@ pub fn install_cli(app: tauri::AppHandle) -> Result<String, String> {
     Ok(install_path.to_string_lossy().to_string())
 }
 
+/// Installs the CLI on Windows by copying the sidecar binary into
+/// `%LOCALAPPDATA%\opencode\bin` and registering that directory on the
+/// current user's `PATH`, mirroring what `INSTALL_SCRIPT` does on unix.
+#[cfg(windows)]
+fn install_cli_windows(sidecar: &std::path::Path) -> Result<String, String> {
+    let install_dir = get_cli_install_dir()
+        .ok_or_else(|| "Could not determine install directory (%LOCALAPPDATA% not set)".to_string())?;
+
+    std::fs::create_dir_all(&install_dir)
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+    let install_path = install_dir.join(CLI_BINARY_NAME);
+
+    std::fs::copy(sidecar, &install_path)
+        .map_err(|e| format!("Failed to copy CLI binary: {}", e))?;
+
+    add_dir_to_user_path(&install_dir)?;
+
+    Ok(install_path.to_string_lossy().to_string())
+}
+
+/// Adds `dir` to the current user's `PATH` registry value, if it isn't
+/// already there. Already-open shells won't see the change until they're
+/// restarted, same as running `setx` by hand.
+#[cfg(windows)]
+fn add_dir_to_user_path(dir: &std::path::Path) -> Result<(), String> {
+    use winreg::RegKey;
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_READ, KEY_WRITE};
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let env = hkcu
+        .open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
+        .map_err(|e| format!("Failed to open user Environment key: {}", e))?;
+
+    let current_path: String = env.get_value("Path").unwrap_or_default();
+    let dir_str = dir.to_string_lossy().to_string();
+
+    if current_path.split(';').any(|p| p.eq_ignore_ascii_case(&dir_str)) {
+        return Ok(());
+    }
+
+    let new_path = if current_path.is_empty() {
+        dir_str
+    } else {
+        format!("{current_path};{dir_str}")
+    };
+
+    env.set_value("Path", &new_path)
+        .map_err(|e| format!("Failed to update user PATH: {}", e))
+}
+
+/// Removes `dir` from the current user's `PATH` registry value, if present.
+#[cfg(windows)]
+fn remove_dir_from_user_path(dir: &std::path::Path) -> Result<(), String> {
+    use winreg::RegKey;
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_READ, KEY_WRITE};
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let env = hkcu
+        .open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
+        .map_err(|e| format!("Failed to open user Environment key: {}", e))?;
+
+    let current_path: String = env.get_value("Path").unwrap_or_default();
+    let dir_str = dir.to_string_lossy().to_string();
+
+    let new_path = current_path
+        .split(';')
+        .filter(|p| !p.eq_ignore_ascii_case(&dir_str))
+        .collect::<Vec<_>>()
+        .join(";");
+
+    if new_path == current_path {
+        return Ok(());
+    }
+
+    env.set_value("Path", &new_path)
+        .map_err(|e| format!("Failed to update user PATH: {}", e))
+}
+
+/// Uninstalls the CLI, removing the installed binary and (on Windows) the
+/// `PATH` entry we added in [`install_cli`].
+#[tauri::command]
+pub fn uninstall_cli() -> Result<(), String> {
+    let Some(install_dir) = get_cli_install_dir() else {
+        return Ok(());
+    };
+    let install_path = install_dir.join(CLI_BINARY_NAME);
+
+    if install_path.exists() {
+        remove_installed_binary(&install_path)?;
+    }
+
+    #[cfg(windows)]
+    remove_dir_from_user_path(&install_dir)?;
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn remove_installed_binary(path: &std::path::Path) -> Result<(), String> {
+    std::fs::remove_file(path).map_err(|e| format!("Failed to remove installed CLI: {}", e))
+}
+
+/// Deletes the installed binary, falling back to scheduling deletion on next
+/// reboot when it's currently running and Windows refuses to delete it
+/// (`ERROR_ACCESS_DENIED`/`ERROR_SHARING_VIOLATION`).
+#[cfg(windows)]
+fn remove_installed_binary(path: &std::path::Path) -> Result<(), String> {
+    use windows::Win32::Storage::FileSystem::{MOVEFILE_DELAY_UNTIL_REBOOT, MoveFileExW};
+    use windows::core::HSTRING;
+
+    if std::fs::remove_file(path).is_ok() {
+        return Ok(());
+    }
+
+    // The binary is likely in use (e.g. an `opencode` the user is running) -
+    // schedule it for deletion on next reboot rather than failing the
+    // uninstall outright.
+    let wide = HSTRING::from(path.as_os_str());
+    let scheduled = unsafe { MoveFileExW(&wide, None, MOVEFILE_DELAY_UNTIL_REBOOT) };
+
+    if scheduled.is_err() {
+        return Err(format!(
+            "Could not remove {} (file in use) and failed to schedule it for deletion",
+            path.display()
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn sync_cli(app: tauri::AppHandle) -> Result<(), String> {
     if cfg!(debug_assertions) {
         println!("Skipping CLI sync for debug build");
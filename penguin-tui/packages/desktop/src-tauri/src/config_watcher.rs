@@ -0,0 +1,101 @@
+//! Watches the opencode config for `server.hostname`/`server.port` changes
+//! and restarts the sidecar so edits take effect without relaunching the
+//! app.
+
+use crate::cli;
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, path::BaseDirectory};
+
+/// Coalesces bursts of filesystem events (e.g. an editor's save-as-rename)
+/// into a single re-read of the config.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Clone, PartialEq)]
+struct WatchedServerConfig {
+    hostname: Option<String>,
+    port: Option<u32>,
+}
+
+impl WatchedServerConfig {
+    fn from_config(config: &cli::Config) -> Option<Self> {
+        let server = config.server.as_ref()?;
+        Some(Self {
+            hostname: server.hostname.clone(),
+            port: server.port,
+        })
+    }
+}
+
+async fn current_server_config(app: &AppHandle) -> Option<WatchedServerConfig> {
+    cli::get_config(app)
+        .await
+        .as_ref()
+        .and_then(WatchedServerConfig::from_config)
+}
+
+/// Spawns the background watcher. Safe to call once during app setup; it
+/// runs for the lifetime of the app.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(app).await {
+            eprintln!("Config watcher stopped: {e}");
+        }
+    });
+}
+
+async fn run(app: AppHandle) -> notify::Result<()> {
+    let state_dir = app
+        .path()
+        .resolve("", BaseDirectory::AppLocalData)
+        .map_err(|e| notify::Error::generic(&e.to_string()))?;
+
+    let (tx, mut rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })?;
+    watcher.watch(&state_dir, notify::RecursiveMode::Recursive)?;
+
+    let mut last_known = current_server_config(&app).await;
+
+    loop {
+        // The actual wait - blocking for the first event, then draining
+        // whatever follows within the debounce window so a burst (e.g.
+        // remove-then-rewrite) collapses into one re-read - is synchronous,
+        // so it runs on a blocking-pool thread rather than parking one of
+        // the runtime's async worker threads for the app's whole lifetime.
+        // `rx` is moved in and handed back so the next iteration can reuse
+        // the same channel/watcher.
+        let (rx_back, connected) = tauri::async_runtime::spawn_blocking(move || {
+            if rx.recv().is_err() {
+                return (rx, false);
+            }
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(_) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return (rx, false),
+                }
+            }
+            (rx, true)
+        })
+        .await
+        .expect("config watcher debounce task panicked");
+        rx = rx_back;
+
+        if !connected {
+            break;
+        }
+
+        let current = current_server_config(&app).await;
+        if current == last_known {
+            continue;
+        }
+
+        println!("opencode config changed, restarting sidecar");
+        last_known = current;
+        crate::restart_sidecar(&app).await;
+    }
+
+    Ok(())
+}
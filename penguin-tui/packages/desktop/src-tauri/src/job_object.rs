@@ -11,8 +11,6 @@
 //! - The RunEvent::Exit handler fails to run
 
 use std::io::{Error, Result};
-#[cfg(windows)]
-use std::sync::Mutex;
 use windows::Win32::Foundation::{CloseHandle, HANDLE};
 use windows::Win32::System::JobObjects::{
     AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
@@ -93,46 +91,6 @@ impl Drop for JobObject {
     }
 }
 
-/// Holds the Windows Job Object that ensures child processes are killed when the app exits.
-/// On Windows, when the job object handle is closed (including on crash), all assigned
-/// processes are automatically terminated by the OS.
-#[cfg(windows)]
-pub struct JobObjectState {
-    job: Mutex<Option<JobObject>>,
-    error: Mutex<Option<String>>,
-}
-
-#[cfg(windows)]
-impl JobObjectState {
-    pub fn new() -> Self {
-        match JobObject::new() {
-            Ok(job) => Self {
-                job: Mutex::new(Some(job)),
-                error: Mutex::new(None),
-            },
-            Err(e) => {
-                eprintln!("Failed to create job object: {e}");
-                Self {
-                    job: Mutex::new(None),
-                    error: Mutex::new(Some(format!("Failed to create job object: {e}"))),
-                }
-            }
-        }
-    }
-
-    pub fn assign_pid(&self, pid: u32) {
-        if let Some(job) = self.job.lock().unwrap().as_ref() {
-            if let Err(e) = job.assign_pid(pid) {
-                eprintln!("Failed to assign process {pid} to job object: {e}");
-                *self.error.lock().unwrap() =
-                    Some(format!("Failed to assign process to job object: {e}"));
-            } else {
-                println!("Assigned process {pid} to job object for automatic cleanup");
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
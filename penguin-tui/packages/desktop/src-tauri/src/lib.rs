@@ -1,21 +1,26 @@
 mod cli;
+mod config_watcher;
 #[cfg(windows)]
 mod job_object;
 mod markdown;
+mod origin_guard;
+mod port_owner;
+mod process_guard;
+mod server_profiles;
+mod sidecar_log;
+mod sidecar_transport;
 mod window_customizer;
 
-use cli::{install_cli, sync_cli};
+use cli::{install_cli, sync_cli, uninstall_cli};
 use futures::FutureExt;
 use futures::future;
-#[cfg(windows)]
-use job_object::*;
+use process_guard::ProcessGuard;
+use sidecar_log::{LogLevel, LogState};
 use std::{
-    collections::VecDeque,
-    net::TcpListener,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
-use tauri::{AppHandle, LogicalSize, Manager, RunEvent, State, WebviewWindowBuilder};
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, RunEvent, State, WebviewWindowBuilder};
 #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
 use tauri_plugin_deep_link::DeepLinkExt;
 #[cfg(windows)]
@@ -36,32 +41,251 @@ struct ServerReadyData {
     password: Option<String>,
 }
 
+type ServerStatus = future::Shared<oneshot::Receiver<Result<ServerReadyData, String>>>;
+
 #[derive(Clone)]
-struct ServerState {
+pub(crate) struct ServerState {
     child: Arc<Mutex<Option<CommandChild>>>,
-    status: future::Shared<oneshot::Receiver<Result<ServerReadyData, String>>>,
+    transport: Arc<Mutex<Option<sidecar_transport::Transport>>>,
+    status: Arc<Mutex<ServerStatus>>,
 }
 
 impl ServerState {
     pub fn new(
         child: Option<CommandChild>,
+        transport: Option<sidecar_transport::Transport>,
         status: oneshot::Receiver<Result<ServerReadyData, String>>,
     ) -> Self {
         Self {
             child: Arc::new(Mutex::new(child)),
-            status: status.shared(),
+            transport: Arc::new(Mutex::new(transport)),
+            status: Arc::new(Mutex::new(status.shared())),
         }
     }
 
-    pub fn set_child(&self, child: Option<CommandChild>) {
+    /// Updates the live child/transport without touching `status` - used
+    /// during the very first connection attempt, where `status`'s one-shot
+    /// channel is already wired up by the caller and gets fulfilled
+    /// separately once that attempt resolves.
+    pub fn set_connection(
+        &self,
+        child: Option<CommandChild>,
+        transport: Option<sidecar_transport::Transport>,
+    ) {
         *self.child.lock().unwrap() = child;
+        *self.transport.lock().unwrap() = transport;
+    }
+
+    /// Swaps in a freshly (re)connected sidecar wholesale: child, transport,
+    /// *and* a new `status` receiver - so callers awaiting it
+    /// (`ensure_server_ready`, `shutdown_sidecar`, `origin_guard`) see the
+    /// new connection's URL/password instead of the very first one forever.
+    /// Used by `restart_sidecar`, where - unlike the initial connection -
+    /// the outcome is already known by the time we touch `ServerState`.
+    pub fn reconnect(
+        &self,
+        child: Option<CommandChild>,
+        transport: Option<sidecar_transport::Transport>,
+        status: oneshot::Receiver<Result<ServerReadyData, String>>,
+    ) {
+        self.set_connection(child, transport);
+        *self.status.lock().unwrap() = status.shared();
+    }
+
+    fn status(&self) -> ServerStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// The URL we're currently connected to, if the server is up. Used by
+    /// `origin_guard` to trust IPC calls coming from that origin.
+    pub(crate) fn current_url(&self) -> Option<String> {
+        self.status().now_or_never()?.ok()?.ok().map(|r| r.url)
     }
 }
 
-#[derive(Clone)]
-struct LogState(Arc<Mutex<VecDeque<String>>>);
+/// How long `shutdown_sidecar` waits for a graceful stop before falling
+/// back to the hard kill path.
+const SIDECAR_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Emitted after [`restart_sidecar`] brings a new sidecar up, with the new
+/// [`ServerReadyData`] as payload, so the frontend can reconnect.
+const SIDECAR_RESTARTED_EVENT: &str = "sidecar-restarted";
+
+/// Tears down the current sidecar (if we spawned one) and respawns it,
+/// emitting [`SIDECAR_RESTARTED_EVENT`] so the frontend can reconnect.
+/// Used by [`config_watcher`] when the opencode config changes.
+pub(crate) async fn restart_sidecar(app: &AppHandle) {
+    shutdown_sidecar(app, SIDECAR_SHUTDOWN_TIMEOUT).await;
+
+    let custom_url = resolve_custom_url(app).await;
+
+    match setup_server_connection(app, custom_url).await {
+        Ok((child, transport, ready)) => {
+            if let Some(child) = &child {
+                app.state::<ProcessGuard>().assign_pid(child.pid());
+            }
+
+            // A fresh `status` receiver, not the original one - each
+            // restart mints a new password/bridge port, and stale
+            // `ensure_server_ready`/`shutdown_sidecar` reads of the very
+            // first connection's data would otherwise live forever.
+            let (tx, rx) = oneshot::channel();
+            let _ = tx.send(Ok(ready.clone()));
+            app.state::<ServerState>().reconnect(child, transport, rx);
+
+            let _ = app.emit(SIDECAR_RESTARTED_EVENT, ready);
+        }
+        Err(e) => eprintln!("Failed to restart sidecar: {e}"),
+    }
+}
+
+/// Attempts to stop the opencode sidecar gracefully - so it gets a chance to
+/// flush state and close sessions - before falling back to the hard kill
+/// path ([`kill_sidecar`], which kills the child process itself; its
+/// process group and the Windows job object are not signalled here).
+///
+/// Requests a graceful stop (`SIGTERM` on unix, an HTTP shutdown request
+/// everywhere), then polls the server's health endpoint until it stops
+/// responding or `timeout` elapses.
+async fn shutdown_sidecar(app: &AppHandle, timeout: Duration) {
+    let Some(server_state) = app.try_state::<ServerState>() else {
+        return;
+    };
+
+    let has_child = server_state
+        .child
+        .lock()
+        .expect("Failed to acquire mutex lock")
+        .is_some();
+
+    if !has_child {
+        // Connected to an externally managed server - nothing to shut down.
+        return;
+    }
+
+    let Ok(Ok(ready)) = server_state.status().await else {
+        return;
+    };
+
+    let Some(transport) = server_state.transport.lock().unwrap().clone() else {
+        return;
+    };
+
+    println!("Requesting graceful shutdown of sidecar at {}", ready.url);
+
+    #[cfg(unix)]
+    {
+        let pid = server_state
+            .child
+            .lock()
+            .expect("Failed to acquire mutex lock")
+            .as_ref()
+            .map(|c| c.pid());
+
+        if let Some(pid) = pid {
+            unsafe {
+                libc::kill(pid as i32, libc::SIGTERM);
+            }
+        }
+    }
+
+    // Sent directly over the sidecar's pipe/socket transport rather than
+    // through reqwest, so a misconfigured system proxy can't swallow it.
+    let _ = transport
+        .request("POST", "/global/shutdown", ready.password.as_deref())
+        .await;
+
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if transport
+            .request("GET", "/global/health", ready.password.as_deref())
+            .await
+            .is_err()
+        {
+            println!("Sidecar exited gracefully");
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    println!("Sidecar did not exit within {timeout:?}, forcing termination");
+    kill_sidecar(app.clone());
+}
+
+/// Opens `url` in the system default browser instead of navigating the app
+/// window, for links the renderer marks `a.external-link` (the anchors
+/// `markdown::ExternalLinkFormatter` produces) - the frontend should attach
+/// a click interceptor on that class that calls this instead of letting the
+/// webview follow `target="_blank"` itself.
+///
+/// Only `http`/`https`/`mailto` URLs are allowed, and the launched helper's
+/// exit status is surfaced as `Err` (rather than assumed successful) so the
+/// frontend can show "couldn't open link" feedback.
+#[tauri::command]
+async fn open_external(url: String) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("Invalid URL: {e}"))?;
+
+    match parsed.scheme() {
+        "http" | "https" | "mailto" => {}
+        scheme => return Err(format!("Refusing to open unsupported URL scheme: {scheme}")),
+    }
+
+    #[cfg(windows)]
+    return open_external_windows(&url);
+
+    #[cfg(not(windows))]
+    {
+        let program = if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+
+        let status = std::process::Command::new(program)
+            .arg(&url)
+            .status()
+            .map_err(|e| format!("Failed to launch system browser: {e}"))?;
+
+        if !status.success() {
+            return Err(format!(
+                "System browser helper exited with status {status}"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Launches `url` via `ShellExecuteW` rather than shelling out through
+/// `cmd.exe`'s `start`: `Command::args` quoting targets `CommandLineToArgvW`-
+/// style consumers, not `cmd.exe`'s own batch-line lexer, which treats
+/// `&`/`|`/`^`/`%` specially regardless of that quoting - so a URL clicked
+/// from untrusted content (chat, rendered markdown) could break out of the
+/// intended `start` invocation. `ShellExecuteW` invokes the URL's registered
+/// handler directly, with no shell in between.
+#[cfg(windows)]
+fn open_external_windows(url: &str) -> Result<(), String> {
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+    use windows::core::HSTRING;
+
+    let operation = HSTRING::from("open");
+    let file = HSTRING::from(url);
+
+    let result =
+        unsafe { ShellExecuteW(None, &operation, &file, None, None, SW_SHOWNORMAL) };
+
+    // ShellExecuteW predates real error codes: it returns a value <= 32 on
+    // failure despite the return type being an `HINSTANCE`.
+    if result.0 as isize <= 32 {
+        return Err(format!(
+            "Failed to launch system browser: error code {}",
+            result.0 as isize
+        ));
+    }
 
-const MAX_LOG_ENTRIES: usize = 200;
+    Ok(())
+}
 
 #[tauri::command]
 fn kill_sidecar(app: AppHandle) {
@@ -85,22 +309,41 @@ fn kill_sidecar(app: AppHandle) {
     println!("Killed server");
 }
 
+#[tauri::command]
 async fn get_logs(app: AppHandle) -> Result<String, String> {
     let log_state = app.try_state::<LogState>().ok_or("Log state not found")?;
+    log_state.snapshot()
+}
 
-    let logs = log_state
-        .0
-        .lock()
-        .map_err(|_| "Failed to acquire log lock")?;
+/// Path to the on-disk rotating sidecar log file, for an "open log folder"
+/// action in the UI. `None` if it couldn't be resolved at startup.
+#[tauri::command]
+fn get_log_file_path(app: AppHandle) -> Result<Option<String>, String> {
+    let log_state = app.try_state::<LogState>().ok_or("Log state not found")?;
+    Ok(log_state
+        .log_file_path()
+        .map(|p| p.to_string_lossy().into_owned()))
+}
 
-    Ok(logs.iter().cloned().collect::<Vec<_>>().join(""))
+/// Whether the sidecar is up, without revealing how to talk to it - safe for
+/// any origin to poll. See [`get_server_connection`] for the privileged
+/// counterpart that returns the actual URL/password.
+#[tauri::command]
+async fn ensure_server_ready(state: State<'_, ServerState>) -> Result<bool, String> {
+    Ok(matches!(state.status().await, Ok(Ok(_))))
 }
 
+/// Returns the live connection details needed to actually talk to the
+/// sidecar. Privileged: `password` is the only thing authenticating
+/// requests over the loopback bridge (`sidecar_transport`'s
+/// `spawn_loopback_bridge` is an unauthenticated raw byte relay otherwise),
+/// so handing it to an untrusted origin would let that origin hit the
+/// sidecar's HTTP API directly, bypassing every other trusted-origin-only
+/// restriction.
 #[tauri::command]
-async fn ensure_server_ready(state: State<'_, ServerState>) -> Result<ServerReadyData, String> {
+async fn get_server_connection(state: State<'_, ServerState>) -> Result<ServerReadyData, String> {
     state
-        .status
-        .clone()
+        .status()
         .await
         .map_err(|_| "Failed to get server status".to_string())?
 }
@@ -140,63 +383,49 @@ async fn set_default_server_url(app: AppHandle, url: Option<String>) -> Result<(
     Ok(())
 }
 
-fn get_sidecar_port() -> u32 {
-    option_env!("OPENCODE_PORT")
-        .map(|s| s.to_string())
-        .or_else(|| std::env::var("OPENCODE_PORT").ok())
-        .and_then(|port_str| port_str.parse().ok())
-        .unwrap_or_else(|| {
-            TcpListener::bind("127.0.0.1:0")
-                .expect("Failed to bind to find free port")
-                .local_addr()
-                .expect("Failed to get local address")
-                .port()
-        }) as u32
-}
+/// Spawns the opencode sidecar listening on `transport` (a fresh named
+/// pipe/Unix socket from [`sidecar_transport::Transport::new_local`], or a
+/// `hostname`/`port` pair for a [`sidecar_transport::Transport::Tcp`] we
+/// don't actually spawn against - see [`setup_server_connection`]).
+fn spawn_sidecar(
+    app: &AppHandle,
+    transport: &sidecar_transport::Transport,
+    password: &str,
+) -> CommandChild {
+    let log_state = app.state::<LogState>().inner().clone();
+    let app_handle = app.clone();
+
+    let args = match transport {
+        sidecar_transport::Transport::Tcp { hostname, port } => {
+            println!("spawning sidecar on {hostname}:{port}");
+            format!("serve --hostname {hostname} --port {port}")
+        }
+        sidecar_transport::Transport::Local(_) => {
+            println!("spawning sidecar on local transport");
+            "serve".to_string()
+        }
+    };
 
-fn spawn_sidecar(app: &AppHandle, hostname: &str, port: u32, password: &str) -> CommandChild {
-    let log_state = app.state::<LogState>();
-    let log_state_clone = log_state.inner().clone();
+    let mut command = cli::create_command(app, &args)
+        .env("OPENCODE_SERVER_USERNAME", "opencode")
+        .env("OPENCODE_SERVER_PASSWORD", password);
 
-    println!("spawning sidecar on port {port}");
+    if let Some((key, value)) = transport.child_env() {
+        command = command.env(key, value);
+    }
 
-    let (mut rx, child) = cli::create_command(
-        app,
-        format!("serve --hostname {hostname} --port {port}").as_str(),
-    )
-    .env("OPENCODE_SERVER_USERNAME", "opencode")
-    .env("OPENCODE_SERVER_PASSWORD", password)
-    .spawn()
-    .expect("Failed to spawn opencode");
+    let (mut rx, child) = command.spawn().expect("Failed to spawn opencode");
 
     tauri::async_runtime::spawn(async move {
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line_bytes) => {
-                    let line = String::from_utf8_lossy(&line_bytes);
-                    print!("{line}");
-
-                    // Store log in shared state
-                    if let Ok(mut logs) = log_state_clone.0.lock() {
-                        logs.push_back(format!("[STDOUT] {}", line));
-                        // Keep only the last MAX_LOG_ENTRIES
-                        while logs.len() > MAX_LOG_ENTRIES {
-                            logs.pop_front();
-                        }
-                    }
+                    let line = String::from_utf8_lossy(&line_bytes).into_owned();
+                    log_state.push(&app_handle, LogLevel::Stdout, line);
                 }
                 CommandEvent::Stderr(line_bytes) => {
-                    let line = String::from_utf8_lossy(&line_bytes);
-                    eprint!("{line}");
-
-                    // Store log in shared state
-                    if let Ok(mut logs) = log_state_clone.0.lock() {
-                        logs.push_back(format!("[STDERR] {}", line));
-                        // Keep only the last MAX_LOG_ENTRIES
-                        while logs.len() > MAX_LOG_ENTRIES {
-                            logs.pop_front();
-                        }
-                    }
+                    let line = String::from_utf8_lossy(&line_bytes).into_owned();
+                    log_state.push(&app_handle, LogLevel::Stderr, line);
                 }
                 _ => {}
             }
@@ -248,6 +477,26 @@ async fn check_server_health(url: &str, password: Option<&str>) -> bool {
         .unwrap_or(false)
 }
 
+/// Whether a healthy `custom_url` is safe to connect to without a password.
+/// A non-loopback URL is a server the user deliberately pointed us at, so
+/// the local port-squat concern doesn't apply; a loopback one must also
+/// pass [`port_owner::is_port_owned_by_sidecar`], confirming whatever's
+/// listening there is actually our CLI and not an unrelated process that
+/// happened to grab the port first.
+fn is_custom_url_trusted(url: &str) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return false;
+    };
+
+    if !url_is_localhost(&parsed) {
+        return true;
+    }
+
+    parsed
+        .port_or_known_default()
+        .is_some_and(port_owner::is_port_owned_by_sidecar)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let updater_enabled = option_env!("TAURI_SIGNING_PRIVATE_KEY").is_some();
@@ -285,14 +534,45 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .plugin(PinchZoomDisablePlugin)
         .plugin(tauri_plugin_decorum::init())
-        .invoke_handler(tauri::generate_handler![
-            kill_sidecar,
-            install_cli,
-            ensure_server_ready,
-            get_default_server_url,
-            set_default_server_url,
-            markdown::parse_markdown_command
-        ])
+        .invoke_handler({
+            let command_handler = tauri::generate_handler![
+                kill_sidecar,
+                install_cli,
+                uninstall_cli,
+                ensure_server_ready,
+                get_server_connection,
+                get_default_server_url,
+                set_default_server_url,
+                open_external,
+                get_logs,
+                get_log_file_path,
+                server_profiles::list_server_profiles,
+                server_profiles::upsert_server_profile,
+                server_profiles::delete_server_profile,
+                server_profiles::set_active_server_profile,
+                markdown::parse_markdown_command
+            ];
+
+            move |invoke| {
+                let command = invoke.message.command();
+
+                if origin_guard::is_privileged(command) {
+                    let webview = invoke.message.webview();
+                    let trusted = webview
+                        .url()
+                        .is_ok_and(|url| origin_guard::is_trusted_origin(&url, webview.app_handle()));
+
+                    if !trusted {
+                        invoke.resolver.reject(format!(
+                            "Command `{command}` is not allowed from this origin"
+                        ));
+                        return true;
+                    }
+                }
+
+                command_handler(invoke)
+            }
+        })
         .setup(move |app| {
             #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
             app.deep_link().register_all().ok();
@@ -300,10 +580,9 @@ pub fn run() {
             let app = app.handle().clone();
 
             // Initialize log state
-            app.manage(LogState(Arc::new(Mutex::new(VecDeque::new()))));
+            app.manage(LogState::new(&app));
 
-            #[cfg(windows)]
-            app.manage(JobObjectState::new());
+            app.manage(ProcessGuard::new().expect("Failed to initialize process guard"));
 
             let primary_monitor = app.primary_monitor().ok().flatten();
             let size = primary_monitor
@@ -350,35 +629,20 @@ pub fn run() {
             let _ = window.create_overlay_titlebar();
 
             let (tx, rx) = oneshot::channel();
-            app.manage(ServerState::new(None, rx));
+            app.manage(ServerState::new(None, None, rx));
 
             {
                 let app = app.clone();
                 tauri::async_runtime::spawn(async move {
-                    let mut custom_url = None;
-
-                    if let Some(url) = get_default_server_url(app.clone()).ok().flatten() {
-                        println!("Using desktop-specific custom URL: {url}");
-                        custom_url = Some(url);
-                    }
-
-                    if custom_url.is_none()
-                        && let Some(cli_config) = cli::get_config(&app).await
-                        && let Some(url) = get_server_url_from_config(&cli_config)
-                    {
-                        println!("Using custom server URL from config: {url}");
-                        custom_url = Some(url);
-                    }
+                    let custom_url = resolve_custom_url(&app).await;
 
                     let res = match setup_server_connection(&app, custom_url).await {
-                        Ok((child, url)) => {
-                            #[cfg(windows)]
+                        Ok((child, transport, url)) => {
                             if let Some(child) = &child {
-                                let job_state = app.state::<JobObjectState>();
-                                job_state.assign_pid(child.pid());
+                                app.state::<ProcessGuard>().assign_pid(child.pid());
                             }
 
-                            app.state::<ServerState>().set_child(child);
+                            app.state::<ServerState>().set_connection(child, transport);
 
                             Ok(url)
                         }
@@ -398,6 +662,8 @@ pub fn run() {
                 });
             }
 
+            config_watcher::spawn(app.clone());
+
             Ok(())
         });
 
@@ -408,12 +674,26 @@ pub fn run() {
     builder
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
-        .run(|app, event| {
-            if let RunEvent::Exit = event {
+        .run(|app, event| match event {
+            RunEvent::ExitRequested { code: None, api, .. } => {
+                // Pause a user-initiated exit so we can try a graceful
+                // shutdown first. The `app.exit(0)` below comes back here
+                // with `Some(0)` and is let through; `Exit` then still
+                // force-kills whatever is left.
+                api.prevent_exit();
+
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    shutdown_sidecar(&app, SIDECAR_SHUTDOWN_TIMEOUT).await;
+                    app.exit(0);
+                });
+            }
+            RunEvent::Exit => {
                 println!("Received Exit");
 
                 kill_sidecar(app.clone());
             }
+            _ => {}
         });
 }
 
@@ -450,15 +730,41 @@ fn get_server_url_from_config(config: &cli::Config) -> Option<String> {
     Some(format!("http://{}:{}", hostname, port))
 }
 
+/// Resolves the URL [`setup_server_connection`] should try instead of
+/// spawning a local sidecar, in priority order: the active
+/// [`server_profiles`] entry, then the legacy single `defaultServerUrl`,
+/// then a `server.port` set in the opencode config. `None` means spawn
+/// local.
+async fn resolve_custom_url(app: &AppHandle) -> Option<String> {
+    if let Some(url) = server_profiles::active_profile_url(app) {
+        return Some(url);
+    }
+
+    if let Some(url) = get_default_server_url(app.clone()).ok().flatten() {
+        return Some(url);
+    }
+
+    let cli_config = cli::get_config(app).await?;
+    get_server_url_from_config(&cli_config)
+}
+
 async fn setup_server_connection(
     app: &AppHandle,
     custom_url: Option<String>,
-) -> Result<(Option<CommandChild>, ServerReadyData), String> {
+) -> Result<
+    (
+        Option<CommandChild>,
+        Option<sidecar_transport::Transport>,
+        ServerReadyData,
+    ),
+    String,
+> {
     if let Some(url) = custom_url {
         loop {
-            if check_server_health(&url, None).await {
+            if check_server_health(&url, None).await && is_custom_url_trusted(&url) {
                 println!("Connected to custom server: {}", url);
                 return Ok((
+                    None,
                     None,
                     ServerReadyData {
                         url: url.clone(),
@@ -486,42 +792,35 @@ async fn setup_server_connection(
         }
     }
 
-    let local_port = get_sidecar_port();
-    let hostname = "127.0.0.1";
-    let local_url = format!("http://{hostname}:{local_port}");
-
-    if !check_server_health(&local_url, None).await {
-        let password = uuid::Uuid::new_v4().to_string();
-
-        match spawn_local_server(app, hostname, local_port, &password).await {
-            Ok(child) => Ok((
-                Some(child),
-                ServerReadyData {
-                    url: local_url,
-                    password: Some(password),
-                },
-            )),
-            Err(err) => Err(err),
-        }
-    } else {
-        Ok((
-            None,
-            ServerReadyData {
-                url: local_url,
-                password: None,
-            },
-        ))
-    }
+    let transport = sidecar_transport::Transport::new_local();
+    let password = uuid::Uuid::new_v4().to_string();
+
+    let child = spawn_local_server(app, &transport, &password).await?;
+
+    // The frontend can only `fetch()` plain HTTP, so bridge the pipe/socket
+    // to a loopback port it can talk to.
+    let bridge_port = transport
+        .spawn_loopback_bridge()
+        .await
+        .map_err(|e| format!("Failed to start local proxy for sidecar: {e}"))?
+        .expect("Transport::new_local always returns Transport::Local");
+
+    Ok((
+        Some(child),
+        Some(transport),
+        ServerReadyData {
+            url: format!("http://127.0.0.1:{bridge_port}"),
+            password: Some(password),
+        },
+    ))
 }
 
 async fn spawn_local_server(
     app: &AppHandle,
-    hostname: &str,
-    port: u32,
+    transport: &sidecar_transport::Transport,
     password: &str,
 ) -> Result<CommandChild, String> {
-    let child = spawn_sidecar(app, hostname, port, password);
-    let url = format!("http://{hostname}:{port}");
+    let child = spawn_sidecar(app, transport, password);
 
     let timestamp = Instant::now();
     loop {
@@ -534,7 +833,11 @@ async fn spawn_local_server(
 
         tokio::time::sleep(Duration::from_millis(10)).await;
 
-        if check_server_health(&url, Some(password)).await {
+        if transport
+            .request("GET", "/global/health", Some(password))
+            .await
+            .is_ok_and(|status| (200..300).contains(&status))
+        {
             println!("Server ready after {:?}", timestamp.elapsed());
             break Ok(child);
         }
@@ -1,5 +1,38 @@
 use comrak::{create_formatter, parse_document, Arena, Options, html::ChildRendering, nodes::NodeValue};
 use std::fmt::Write;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// Highlights `code` as `lang_token` (the fence info string's first word),
+/// returning `None` when the language is empty or unrecognized so the
+/// caller can fall back to plain escaped text.
+fn highlight_code(code: &str, lang_token: &str) -> Option<String> {
+    if lang_token.is_empty() {
+        return None;
+    }
+
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang_token)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(lang_token))?;
+
+    let theme = &THEME_SET.themes["base16-ocean.dark"];
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let mut html = String::new();
+    for line in LinesWithEndings::from(code) {
+        let ranges = highlighter.highlight_line(line, &SYNTAX_SET).ok()?;
+        html.push_str(&styled_line_to_highlighted_html(&ranges[..], IncludeBackground::No).ok()?);
+    }
+
+    Some(html)
+}
 
 create_formatter!(ExternalLinkFormatter, {
     NodeValue::Link(ref nl) => |context, node, entering| {
@@ -37,24 +70,201 @@ create_formatter!(ExternalLinkFormatter, {
             context.write_str("</a>")?;
         }
     },
+    NodeValue::CodeBlock(ref ncb) => |context, node, entering| {
+        if !entering {
+            return Ok(ChildRendering::HTML);
+        }
+
+        // The fence info string may carry extra metadata after the language
+        // (e.g. `rust,ignore`); only the first token selects the highlighter.
+        let lang = ncb
+            .info
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .next()
+            .unwrap_or("");
+
+        context.write_str("<pre><code")?;
+        comrak::html::render_sourcepos(context, node)?;
+        if !lang.is_empty() {
+            context.write_str(" class=\"language-")?;
+            context.escape(lang)?;
+            context.write_str("\"")?;
+        }
+        context.write_str(">")?;
+
+        // Never trust `highlight_code`'s own escaping for untested paths -
+        // fall back to the formatter's own (HTML-)escaping when the
+        // language is unknown or empty, so copy-to-clipboard still yields
+        // clean, unambiguous source either way.
+        match highlight_code(&ncb.literal, lang) {
+            Some(html) => context.write_str(&html)?,
+            None => context.escape(&ncb.literal)?,
+        }
+
+        context.write_str("</code></pre>")?;
+    },
 });
 
-pub fn parse_markdown(input: &str) -> String {
+/// Selects how raw/dangerous HTML in the rendered output is handled.
+///
+/// Model output and pasted content can carry arbitrary HTML; rendering it
+/// verbatim in a desktop webview is an injection risk. `Unsafe` keeps
+/// today's behavior (trusted content only); `Sanitized` runs the result
+/// through an allowlist cleaner.
+#[derive(Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizeMode {
+    #[default]
+    Unsafe,
+    Sanitized,
+}
+
+/// Tags ammonia lets through in [`SanitizeMode::Sanitized`] mode: enough
+/// for prose, tables, task lists and the syntax-highlighted code blocks
+/// produced by [`highlight_code`], nothing else.
+const ALLOWED_TAGS: &[&str] = &[
+    "p", "br", "hr", "strong", "em", "del", "blockquote", "span",
+    "h1", "h2", "h3", "h4", "h5", "h6",
+    "a", "code", "pre", "img",
+    "ul", "ol", "li", "input",
+    "table", "thead", "tbody", "tr", "th", "td",
+];
+
+/// Restricts a `<span style="...">` value to `color`/`background-color`
+/// declarations with a `#`-hex value - the only thing [`highlight_code`]'s
+/// syntect output ever needs - dropping everything else. Without this,
+/// `style` passing through untouched would let raw attacker/model-supplied
+/// `<span style="...">` in untrusted markdown do things ammonia's own
+/// allowlist doesn't otherwise guard against, like `position:fixed` overlays
+/// or `background:url(...)` exfiltration. Returns `None` (the attribute is
+/// dropped) if nothing in `value` survives the filter.
+fn sanitize_span_style(value: &str) -> Option<String> {
+    let kept: Vec<String> = value
+        .split(';')
+        .filter_map(|decl| {
+            let (prop, val) = decl.split_once(':')?;
+            let prop = prop.trim().to_ascii_lowercase();
+            let val = val.trim();
+            matches!(prop.as_str(), "color" | "background-color" if is_hex_color(val))
+                .then(|| format!("{prop}:{val}"))
+        })
+        .collect();
+
+    (!kept.is_empty()).then(|| kept.join(";"))
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn sanitize_html(html: &str) -> String {
+    ammonia::Builder::empty()
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .url_schemes(["http", "https", "mailto"].into_iter().collect())
+        .link_rel(Some("noopener noreferrer"))
+        .add_tag_attributes("a", ["href", "title", "class", "target"])
+        .add_tag_attributes("img", ["src", "alt", "title"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("span", ["style"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .attribute_filter(|element, attribute, value| {
+            if element == "span" && attribute == "style" {
+                return sanitize_span_style(value);
+            }
+            // `ExternalLinkFormatter` already sets this; ammonia's own
+            // `link_rel` doesn't touch `target`, so keep it, normalized to
+            // `_blank`. An `<a>` that arrives without a `target` (e.g. raw
+            // HTML) is left without one.
+            if element == "a" && attribute == "target" {
+                return Some("_blank".into());
+            }
+            Some(value.into())
+        })
+        .clean(html)
+        .to_string()
+}
+
+pub fn parse_markdown(input: &str, mode: SanitizeMode) -> String {
     let mut options = Options::default();
     options.extension.strikethrough = true;
     options.extension.table = true;
     options.extension.tasklist = true;
     options.extension.autolink = true;
+    // comrak only drops raw HTML rather than cleaning it, so `Sanitized`
+    // mode still parses unsafely here and sanitizes the rendered HTML below.
     options.render.r#unsafe = true;
 
     let arena = Arena::new();
     let doc = parse_document(&arena, input, &options);
     let mut html = String::new();
     ExternalLinkFormatter::format_document(doc, &options, &mut html).unwrap_or_default();
-    html
+
+    match mode {
+        SanitizeMode::Unsafe => html,
+        SanitizeMode::Sanitized => sanitize_html(&html),
+    }
 }
 
 #[tauri::command]
-pub async fn parse_markdown_command(markdown: String) -> Result<String, String> {
-    Ok(parse_markdown(&markdown))
+pub async fn parse_markdown_command(
+    markdown: String,
+    sanitize_mode: SanitizeMode,
+) -> Result<String, String> {
+    Ok(parse_markdown(&markdown, sanitize_mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_known_language() {
+        let html = parse_markdown("```rust\nfn main() {}\n```\n", SanitizeMode::Unsafe);
+        assert!(html.contains(r#"class="language-rust""#));
+        assert!(html.contains("<span style="), "expected highlighted spans: {html}");
+    }
+
+    #[test]
+    fn falls_back_to_escaped_text_for_unknown_language() {
+        let html = parse_markdown(
+            "```bogus-lang\n<script>alert(1)</script>\n```\n",
+            SanitizeMode::Unsafe,
+        );
+        assert!(html.contains(r#"class="language-bogus-lang""#));
+        assert!(!html.contains("<span style="));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn renders_plain_code_for_empty_fence() {
+        let html = parse_markdown("```\nplain text\n```\n", SanitizeMode::Unsafe);
+        assert!(html.contains("<pre><code>"));
+        assert!(!html.contains("class=\"language-"));
+        assert!(html.contains("plain text"));
+    }
+
+    #[test]
+    fn sanitized_mode_strips_raw_html() {
+        let html = parse_markdown(
+            "hello <img src=\"x\" onerror=\"alert(1)\"> [a link](javascript:alert(1))",
+            SanitizeMode::Sanitized,
+        );
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+    }
+
+    #[test]
+    fn sanitized_mode_keeps_highlight_colors_but_strips_other_style_declarations() {
+        let html = parse_markdown("```rust\nfn main() {}\n```\n", SanitizeMode::Sanitized);
+        assert!(html.contains("<span style="), "expected highlighted spans: {html}");
+
+        let raw = r#"<span style="color:#abc; position:fixed; background:url(https://evil.example)">hi</span>"#;
+        let cleaned = sanitize_html(raw);
+        assert!(cleaned.contains(r#"style="color:#abc""#), "{cleaned}");
+        assert!(!cleaned.contains("position"));
+        assert!(!cleaned.contains("evil.example"));
+    }
 }
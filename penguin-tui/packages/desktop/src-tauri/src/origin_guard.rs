@@ -0,0 +1,67 @@
+//! Restricts privileged IPC commands to the trusted local app origin.
+//!
+//! The webview can navigate to or embed remote server UIs, so a compromised
+//! or remote page must not be able to invoke commands that control the
+//! sidecar process, repoint where the app connects (e.g. `kill_sidecar`,
+//! `set_default_server_url`, `install_cli`), or read the loopback bridge
+//! password (`get_server_connection`) - that password is the only thing
+//! authenticating requests to the sidecar's HTTP API, so leaking it is as
+//! good as leaking direct process control. Commands are split into two
+//! tiers: always-allowed (health/status, safe for any content) and
+//! trusted-origin-only (mutating/process-control/credential-bearing); the
+//! latter are checked against the allowlist below before being dispatched.
+
+use crate::ServerState;
+use tauri::{AppHandle, Manager, Url};
+
+/// Commands that only read status/health and are safe to expose to
+/// whatever content the webview currently has loaded.
+const ALWAYS_ALLOWED_COMMANDS: &[&str] = &[
+    "ensure_server_ready",
+    "get_default_server_url",
+    "list_server_profiles",
+    "parse_markdown_command",
+];
+
+/// Returns `true` if `command` must only be dispatched when it was invoked
+/// from a [`is_trusted_origin`] webview.
+pub fn is_privileged(command: &str) -> bool {
+    !ALWAYS_ALLOWED_COMMANDS.contains(&command)
+}
+
+/// Checks `url` (the calling webview's current location) against the
+/// allowlist: the bundled app origin, plus the server we're actually
+/// connected to (`ServerState::current_url`), so the app's own UI - which
+/// may itself be served off that origin - keeps working.
+pub fn is_trusted_origin(url: &Url, app: &AppHandle) -> bool {
+    // The bundled app UI is served from `tauri://localhost` (or
+    // `https://tauri.localhost` on Windows) in production.
+    if url.scheme() == "tauri" || url.host_str() == Some("tauri.localhost") {
+        return true;
+    }
+
+    // The Vite/webpack dev server runs on loopback in debug builds.
+    if cfg!(debug_assertions)
+        && url
+            .host_str()
+            .is_some_and(|host| host == "localhost" || host == "127.0.0.1")
+    {
+        return true;
+    }
+
+    let Some(server_state) = app.try_state::<ServerState>() else {
+        return false;
+    };
+
+    let Some(trusted_url) = server_state.current_url() else {
+        return false;
+    };
+
+    let Ok(trusted) = Url::parse(&trusted_url) else {
+        return false;
+    };
+
+    trusted.scheme() == url.scheme()
+        && trusted.host() == url.host()
+        && trusted.port_or_known_default() == url.port_or_known_default()
+}
@@ -0,0 +1,48 @@
+//! Verifies that a listening local TCP port actually belongs to our own
+//! `opencode-cli` binary before the app trusts it as an already-running
+//! server.
+//!
+//! Originally this guarded `setup_server_connection`'s "reuse whatever's
+//! already listening on our local port" path, but [`crate::sidecar_transport`]
+//! removed that scenario entirely: the sidecar we spawn ourselves now always
+//! binds a fresh, per-run named pipe/Unix socket rather than a shared TCP
+//! port, so there's nothing local left to blindly trust. The one remaining
+//! place we connect to a pre-existing loopback listener without having
+//! spawned it is a loopback `custom_url` (a user-configured server) - so
+//! that's what this is applied to: before connecting to it without a
+//! password, confirm the process actually listening there is ours rather
+//! than some unrelated process squatting on the port.
+
+use netstat2::{AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState, get_sockets_info};
+use sysinfo::{Pid, System};
+
+#[cfg(windows)]
+const EXPECTED_PROCESS_NAME: &str = "opencode-cli.exe";
+#[cfg(not(windows))]
+const EXPECTED_PROCESS_NAME: &str = "opencode-cli";
+
+/// Returns `true` only if some process is listening on loopback:`port` in
+/// `LISTEN` state and its executable name matches ours. Any ambiguity -
+/// nothing listening, the owning PID can't be resolved, enumerating sockets
+/// or processes fails - is treated as untrusted (`false`); the caller then
+/// doesn't connect and offers a retry or a local sidecar instead.
+pub fn is_port_owned_by_sidecar(port: u16) -> bool {
+    let Ok(sockets) = get_sockets_info(AddressFamilyFlags::IPV4, ProtocolFlags::TCP) else {
+        return false;
+    };
+
+    let owning_pids = sockets.into_iter().filter_map(|socket| {
+        let ProtocolSocketInfo::Tcp(tcp) = socket.protocol_socket_info else {
+            return None;
+        };
+        (tcp.local_port == port && tcp.local_addr.is_loopback() && tcp.state == TcpState::Listen)
+            .then_some(socket.associated_pids)
+    });
+
+    let system = System::new_all();
+
+    owning_pids
+        .flatten()
+        .filter_map(|pid| system.process(Pid::from_u32(pid)))
+        .any(|process| process.name().to_string_lossy() == EXPECTED_PROCESS_NAME)
+}
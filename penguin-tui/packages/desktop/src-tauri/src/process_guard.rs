@@ -0,0 +1,177 @@
+//! Cross-platform child-process reaper.
+//!
+//! [`ProcessGuard`] ensures the spawned `opencode-cli` sidecar can't outlive
+//! us, even if we crash:
+//! - On Windows this wraps a [`JobObject`](crate::job_object::JobObject);
+//!   closing the job handle kills every process assigned to it, which the OS
+//!   does automatically even if we're killed with no chance to run our own
+//!   code.
+//! - On Unix (Linux/macOS) the child is moved into its own process group and
+//!   a standalone watchdog process kills that group the moment it detects
+//!   we're gone - see `unix_impl::spawn_watchdog` for why this, and not
+//!   `Drop`, is what actually survives `SIGKILL`/an OOM-kill/a segfault (none
+//!   of which unwind the stack or run destructors).
+//!
+//! `assign_pid` is safe to call on a pid that has already exited (it's a
+//! no-op, not an error) and the guard is safe to drop twice.
+
+#[cfg(windows)]
+pub use windows_impl::ProcessGuard;
+
+#[cfg(unix)]
+pub use unix_impl::ProcessGuard;
+
+#[cfg(windows)]
+mod windows_impl {
+    use crate::job_object::JobObject;
+
+    /// Holds the Windows Job Object that ensures the sidecar is killed when
+    /// the app exits. On Windows, when the job object handle is closed
+    /// (including on crash), all assigned processes are automatically
+    /// terminated by the OS.
+    pub struct ProcessGuard {
+        job: Option<JobObject>,
+    }
+
+    impl ProcessGuard {
+        pub fn new() -> std::io::Result<Self> {
+            Ok(Self {
+                job: Some(JobObject::new()?),
+            })
+        }
+
+        /// Assigns `pid` to the job object. A pid that has already exited
+        /// fails `AssignProcessToJobObject`; that's fine, there's nothing
+        /// left to reap, so we just log it.
+        pub fn assign_pid(&self, pid: u32) {
+            let Some(job) = &self.job else { return };
+
+            if let Err(e) = job.assign_pid(pid) {
+                eprintln!("Failed to assign sidecar {pid} to job object: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix_impl {
+    use std::fs::File;
+    use std::os::unix::io::FromRawFd;
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    use std::sync::Mutex;
+
+    /// Puts the spawned child into its own process group and keeps a
+    /// [`spawn_watchdog`] reaper alive for it, so the sidecar is killed even
+    /// if we're killed with no chance to run our own shutdown code (or any
+    /// destructor - see `spawn_watchdog`).
+    pub struct ProcessGuard {
+        pgid: Mutex<Option<libc::pid_t>>,
+        /// Write end of the watchdog's pipe, held open for as long as the
+        /// current sidecar should stay alive. Never read or written to -
+        /// its only purpose is to keep the fd open; replacing or dropping it
+        /// closes it, which is what wakes the watchdog up.
+        watchdog_pipe: Mutex<Option<File>>,
+    }
+
+    impl ProcessGuard {
+        pub fn new() -> std::io::Result<Self> {
+            Ok(Self {
+                pgid: Mutex::new(None),
+                watchdog_pipe: Mutex::new(None),
+            })
+        }
+
+        /// Moves `pid` into its own process group (`setpgid(pid, pid)`) and
+        /// spawns a watchdog that kills that group the moment it detects us
+        /// going away.
+        ///
+        /// `tauri_plugin_shell` spawns the child for us, so we can't attach
+        /// `PR_SET_PDEATHSIG`/a `pre_exec` hook before it execs; `setpgid`
+        /// called from here right after spawn is racy for that brief window
+        /// but is still correct once the group is set. A pid that has
+        /// already exited (`ESRCH`) is a no-op, not an error.
+        pub fn assign_pid(&self, pid: u32) {
+            let pid = pid as libc::pid_t;
+
+            if unsafe { libc::setpgid(pid, pid) } != 0 {
+                let err = std::io::Error::last_os_error();
+                if err.raw_os_error() != Some(libc::ESRCH) {
+                    eprintln!("Failed to move sidecar {pid} into its own process group: {err}");
+                }
+                return;
+            }
+
+            *self.pgid.lock().unwrap() = Some(pid);
+            self.spawn_watchdog(pid);
+        }
+
+        /// Spawns a standalone `sh` watchdog, independent of
+        /// `tauri_plugin_shell`, that blocks reading its stdin - the read
+        /// end of a pipe whose write end we hold open in `watchdog_pipe` -
+        /// and kills `pgid`'s process group the instant that read unblocks.
+        ///
+        /// The kernel closes every fd a process holds when that process
+        /// dies for *any* reason: a normal exit, `SIGKILL`, an OOM-kill, a
+        /// segfault. That's what wakes the watchdog (its `read` returns
+        /// `EOF`), so - unlike `Drop`, which only runs on an orderly unwind
+        /// and therefore never fires for exactly the crash cases this guard
+        /// exists for - this reaps the sidecar regardless of how we go down.
+        /// Replacing/dropping `watchdog_pipe` (e.g. the next `assign_pid`,
+        /// for a respawned sidecar) closes the old pipe and wakes the old
+        /// watchdog too; killing an already-dead group is a harmless no-op.
+        fn spawn_watchdog(&self, pgid: libc::pid_t) {
+            let mut fds = [0 as libc::c_int; 2];
+            if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+                eprintln!(
+                    "Failed to create watchdog pipe: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+            // `pipe` doesn't set `FD_CLOEXEC`; without it the watchdog (and
+            // anything spawned later) would inherit the write end, keeping
+            // the pipe open so its `read` never sees EOF.
+            for fd in fds {
+                unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+            }
+            let [read_fd, write_fd] = fds;
+
+            let script = format!("read -r _ <&0; kill -9 -- -{pgid}");
+            let spawned = std::process::Command::new("/bin/sh")
+                .arg("-c")
+                .arg(&script)
+                .stdin(unsafe { Stdio::from_raw_fd(read_fd) })
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                // Its own process group, detached from ours - otherwise a
+                // terminal Ctrl-C (SIGINT to the whole foreground group)
+                // kills the watchdog shell itself before it ever reads EOF,
+                // defeating the one scenario ("regardless of how the parent
+                // dies") this mechanism exists for.
+                .process_group(0)
+                .spawn();
+
+            // `Stdio::from_raw_fd(read_fd)` already took ownership of
+            // `read_fd` - it's closed (in the parent) when `spawned`'s
+            // temporary `Command`/`Stdio` are dropped at the end of this
+            // statement. Closing it again here would be a double-close,
+            // and on a busy multi-threaded process a dangling close like
+            // that can end up closing an unrelated fd that got reused in
+            // between.
+
+            match spawned {
+                Ok(_child) => {
+                    // Not waiting on `_child` is deliberate: it's a
+                    // standalone reaper, not something we manage.
+                    *self.watchdog_pipe.lock().unwrap() =
+                        Some(unsafe { File::from_raw_fd(write_fd) });
+                }
+                Err(e) => {
+                    eprintln!("Failed to spawn sidecar watchdog: {e}");
+                    unsafe { libc::close(write_fd) };
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Named server connection profiles, so a user working against more than
+//! one backend (local sidecar, a remote team server, a staging box) can
+//! save and switch between them instead of re-typing a URL every time.
+//!
+//! Profiles live in the same settings store as the legacy single
+//! `defaultServerUrl` (`get_default_server_url`/`set_default_server_url`),
+//! which remains the fallback used when no profile is active - see
+//! [`crate::resolve_custom_url`].
+
+use crate::SETTINGS_STORE;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const PROFILES_KEY: &str = "serverProfiles";
+const ACTIVE_PROFILE_ID_KEY: &str = "activeProfileId";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct ServerProfile {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(rename = "requiresAuth")]
+    pub requires_auth: bool,
+}
+
+fn read_profiles(app: &AppHandle) -> Result<Vec<ServerProfile>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(PROFILES_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+fn write_profiles(app: &AppHandle, profiles: &[ServerProfile]) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(
+        PROFILES_KEY,
+        serde_json::to_value(profiles).map_err(|e| e.to_string())?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+fn read_active_profile_id(app: &AppHandle) -> Result<Option<String>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(ACTIVE_PROFILE_ID_KEY)
+        .and_then(|v| v.as_str().map(String::from)))
+}
+
+fn write_active_profile_id(app: &AppHandle, id: Option<&str>) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    match id {
+        Some(id) => {
+            store.set(ACTIVE_PROFILE_ID_KEY, serde_json::Value::String(id.to_string()));
+        }
+        None => {
+            store.delete(ACTIVE_PROFILE_ID_KEY);
+        }
+    };
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+pub fn list_server_profiles(app: AppHandle) -> Result<Vec<ServerProfile>, String> {
+    read_profiles(&app)
+}
+
+/// Adds `profile`, or replaces the existing one with the same `id`.
+#[tauri::command]
+pub fn upsert_server_profile(app: AppHandle, profile: ServerProfile) -> Result<(), String> {
+    let mut profiles = read_profiles(&app)?;
+
+    match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+
+    write_profiles(&app, &profiles)
+}
+
+/// Removes the profile with `id`, clearing it as the active profile first
+/// if it was active.
+#[tauri::command]
+pub fn delete_server_profile(app: AppHandle, id: String) -> Result<(), String> {
+    if read_active_profile_id(&app)?.as_deref() == Some(id.as_str()) {
+        write_active_profile_id(&app, None)?;
+    }
+
+    let mut profiles = read_profiles(&app)?;
+    profiles.retain(|p| p.id != id);
+    write_profiles(&app, &profiles)
+}
+
+/// Resolves the active profile's URL, if one is set and still exists.
+pub fn active_profile_url(app: &AppHandle) -> Option<String> {
+    let id = read_active_profile_id(app).ok().flatten()?;
+    read_profiles(app)
+        .ok()?
+        .into_iter()
+        .find(|p| p.id == id)
+        .map(|p| p.url)
+}
+
+/// Sets the active profile (`None` reverts to the legacy
+/// `defaultServerUrl`/config-derived URL), then tears down the current
+/// sidecar connection and reconnects against it - no app restart required.
+#[tauri::command]
+pub async fn set_active_server_profile(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    write_active_profile_id(&app, id.as_deref())?;
+    crate::restart_sidecar(&app).await;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Sidecar stdout/stderr handling shared by every line the sidecar prints:
+//! an in-memory ring `get_logs` reads from, a live `sidecar-log` event for a
+//! frontend console, and a size-capped rotating on-disk file so a crash
+//! outside the ring's window is still diagnosable.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, path::BaseDirectory};
+
+/// How many lines `get_logs` keeps around in memory.
+const MAX_LOG_ENTRIES: usize = 200;
+
+const LOG_FILE_NAME: &str = "sidecar.log";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// How many rotated segments (`sidecar.log.1`, `.2`, ...) are kept besides
+/// the active file.
+const ROTATED_SEGMENTS: u32 = 3;
+
+/// Emitted for every sidecar stdout/stderr line, payload is a [`LogEntry`].
+const SIDECAR_LOG_EVENT: &str = "sidecar-log";
+
+#[derive(Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct LogEntry {
+    level: LogLevel,
+    line: String,
+    ts: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct LogState {
+    ring: Arc<Mutex<VecDeque<String>>>,
+    file: Arc<Mutex<Option<File>>>,
+    file_path: Option<PathBuf>,
+}
+
+impl LogState {
+    /// Resolves the on-disk log path under the app's log dir and opens it
+    /// for appending; persistence is best-effort, so a resolve/open failure
+    /// just leaves `file_path`/`file` empty rather than failing setup.
+    pub fn new(app: &AppHandle) -> Self {
+        let file_path = app
+            .path()
+            .resolve(LOG_FILE_NAME, BaseDirectory::AppLog)
+            .ok();
+        let file = file_path.as_deref().and_then(|p| open_log_file(p).ok());
+
+        Self {
+            ring: Arc::new(Mutex::new(VecDeque::new())),
+            file: Arc::new(Mutex::new(file)),
+            file_path,
+        }
+    }
+
+    pub fn log_file_path(&self) -> Option<PathBuf> {
+        self.file_path.clone()
+    }
+
+    pub fn snapshot(&self) -> Result<String, String> {
+        let ring = self.ring.lock().map_err(|_| "Failed to acquire log lock")?;
+        Ok(ring.iter().cloned().collect::<Vec<_>>().join(""))
+    }
+
+    /// Records one stdout/stderr line: prints it (matching the old
+    /// behavior), pushes it onto the in-memory ring, appends it to the
+    /// rotating log file, and emits a [`SIDECAR_LOG_EVENT`] for a live
+    /// frontend console.
+    pub fn push(&self, app: &AppHandle, level: LogLevel, line: String) {
+        let prefix = match level {
+            LogLevel::Stdout => "[STDOUT] ",
+            LogLevel::Stderr => "[STDERR] ",
+        };
+        let raw = format!("{prefix}{line}");
+        match level {
+            LogLevel::Stdout => print!("{raw}"),
+            LogLevel::Stderr => eprint!("{raw}"),
+        }
+
+        if let Ok(mut ring) = self.ring.lock() {
+            ring.push_back(raw.clone());
+            while ring.len() > MAX_LOG_ENTRIES {
+                ring.pop_front();
+            }
+        }
+
+        self.append_to_file(&raw);
+
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let _ = app.emit(SIDECAR_LOG_EVENT, LogEntry { level, line, ts });
+    }
+
+    fn append_to_file(&self, raw: &str) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+
+        if guard
+            .as_ref()
+            .and_then(|f| f.metadata().ok())
+            .is_some_and(|meta| meta.len() >= MAX_LOG_FILE_BYTES)
+        {
+            rotate(path);
+            *guard = None;
+        }
+
+        if guard.is_none() {
+            *guard = open_log_file(path).ok();
+        }
+
+        if let Some(file) = guard.as_mut() {
+            let _ = file.write_all(raw.as_bytes());
+        }
+    }
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Shifts `sidecar.log.1` -> `.2` -> ... -> `.N`, dropping whatever was
+/// already at `.N`, then moves the active file to `.1`. Best-effort: a
+/// failed rename just means that segment's history is lost, not a hard
+/// error - logging must never be allowed to break the sidecar.
+fn rotate(path: &Path) {
+    let oldest = path.with_extension(format!("log.{ROTATED_SEGMENTS}"));
+    let _ = std::fs::remove_file(oldest);
+
+    for n in (1..ROTATED_SEGMENTS).rev() {
+        let from = path.with_extension(format!("log.{n}"));
+        let to = path.with_extension(format!("log.{}", n + 1));
+        let _ = std::fs::rename(from, to);
+    }
+
+    let _ = std::fs::rename(path, path.with_extension("log.1"));
+}
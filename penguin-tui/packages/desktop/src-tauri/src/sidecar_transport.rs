@@ -0,0 +1,166 @@
+//! Local transport the sidecar listens on.
+//!
+//! Binding a random loopback TCP port means fighting proxy/VPN interference
+//! (`no_proxy()` / `--proxy-bypass-list` in `lib.rs`) and trusting whatever
+//! else might already be listening on it. By default the sidecar we spawn
+//! instead listens on an OS-local transport that only the current user can
+//! reach: a named pipe on Windows, a Unix domain socket everywhere else -
+//! passed to the child via an env var (`OPENCODE_PIPE`/`OPENCODE_SOCKET`).
+//! Since pipe/socket permissions
+//! already restrict access to the current user, no shared-secret password is
+//! needed for requests sent directly over it. TCP remains the transport when
+//! connecting to a `custom_url` (a user-configured server we didn't spawn).
+
+use std::path::PathBuf;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+type BoxedStream = Pin<Box<dyn AsyncReadWrite + Send>>;
+
+trait AsyncReadWrite: AsyncRead + AsyncWrite {}
+impl<T: AsyncRead + AsyncWrite> AsyncReadWrite for T {}
+
+#[derive(Clone, Debug)]
+pub enum Transport {
+    /// `\\.\pipe\opencode-<uuid>` on Windows, a Unix domain socket under
+    /// `$XDG_RUNTIME_DIR` (falling back to the system temp dir) elsewhere.
+    Local(PathBuf),
+    Tcp { hostname: String, port: u32 },
+}
+
+impl Transport {
+    /// Picks the default local transport for a sidecar we're about to spawn.
+    pub fn new_local() -> Self {
+        let id = uuid::Uuid::new_v4();
+
+        #[cfg(windows)]
+        {
+            Self::Local(PathBuf::from(format!(r"\\.\pipe\opencode-{id}")))
+        }
+
+        #[cfg(not(windows))]
+        {
+            let dir = std::env::var_os("XDG_RUNTIME_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(std::env::temp_dir);
+            Self::Local(dir.join(format!("opencode-{id}.sock")))
+        }
+    }
+
+    /// The env var to hand the spawned sidecar so it listens on this
+    /// transport, `None` for [`Transport::Tcp`] (hostname/port are passed as
+    /// CLI args instead, as before).
+    pub fn child_env(&self) -> Option<(&'static str, String)> {
+        match self {
+            #[cfg(windows)]
+            Self::Local(path) => Some(("OPENCODE_PIPE", path.display().to_string())),
+            #[cfg(not(windows))]
+            Self::Local(path) => Some(("OPENCODE_SOCKET", path.display().to_string())),
+            Self::Tcp { .. } => None,
+        }
+    }
+
+    async fn connect(&self) -> std::io::Result<BoxedStream> {
+        match self {
+            Self::Tcp { hostname, port } => {
+                let stream = tokio::net::TcpStream::connect((hostname.as_str(), *port as u16)).await?;
+                Ok(Box::pin(stream))
+            }
+            #[cfg(unix)]
+            Self::Local(socket_path) => {
+                let stream = tokio::net::UnixStream::connect(socket_path).await?;
+                Ok(Box::pin(stream))
+            }
+            #[cfg(windows)]
+            Self::Local(pipe_name) => {
+                let client = tokio::net::windows::named_pipe::ClientOptions::new().open(pipe_name)?;
+                Ok(Box::pin(client))
+            }
+        }
+    }
+
+    /// Sends a bare HTTP/1.1 request directly over this transport (no system
+    /// proxy can intercept a Unix domain socket or named pipe) and returns
+    /// the response status code. Used for our own health checks and the
+    /// graceful-shutdown request.
+    pub async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        password: Option<&str>,
+    ) -> std::io::Result<u16> {
+        send_http_request(self.connect().await?, method, path, password).await
+    }
+
+    /// For `Local` transports, spawns a raw byte-for-byte TCP<->transport
+    /// relay on an ephemeral loopback port and returns it, so the webview
+    /// (which can only `fetch()` plain HTTP) still reaches the sidecar.
+    /// Unlike a real HTTP client, this relay never consults
+    /// `HTTP_PROXY`/system proxy settings since it never parses HTTP at all
+    /// - it just shovels bytes. Returns `None` for `Tcp`, which the frontend
+    /// already talks to directly via `ServerReadyData.url`.
+    pub async fn spawn_loopback_bridge(&self) -> std::io::Result<Option<u16>> {
+        let Self::Local(_) = self else {
+            return Ok(None);
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let transport = self.clone();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let Ok((mut inbound, _)) = listener.accept().await else {
+                    break;
+                };
+                let transport = transport.clone();
+
+                tauri::async_runtime::spawn(async move {
+                    let Ok(mut outbound) = transport.connect().await else {
+                        return;
+                    };
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                });
+            }
+        });
+
+        Ok(Some(port))
+    }
+}
+
+/// Writes a minimal HTTP/1.1 request to `stream` and parses just enough of
+/// the response to return its status code. Good enough for the two calls we
+/// make ourselves (health check, shutdown); the frontend's richer traffic
+/// goes through `spawn_loopback_bridge` instead, untouched by us.
+async fn send_http_request(
+    mut stream: BoxedStream,
+    method: &str,
+    path: &str,
+    password: Option<&str>,
+) -> std::io::Result<u16> {
+    use base64::Engine;
+
+    let mut request =
+        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n");
+    if let Some(password) = password {
+        let creds = base64::engine::general_purpose::STANDARD.encode(format!("opencode:{password}"));
+        request.push_str(&format!("Authorization: Basic {creds}\r\n"));
+    }
+    request.push_str("Content-Length: 0\r\n\r\n");
+
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    let mut headers = [httparse::EMPTY_HEADER; 16];
+    let mut parsed = httparse::Response::new(&mut headers);
+    parsed
+        .parse(&response)
+        .map_err(|e| std::io::Error::other(format!("malformed HTTP response: {e}")))?;
+
+    parsed
+        .code
+        .ok_or_else(|| std::io::Error::other("HTTP response missing status code"))
+}